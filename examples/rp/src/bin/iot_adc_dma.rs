@@ -0,0 +1,245 @@
+#![no_std]
+#![no_main]
+///
+/// Connect a potentiometer to pin 26 and an LED to pin 16.
+/// The LED will turn on when the potentiometer value is greater than 2048.
+///
+/// This is a variant of `iot_adc` that replaces the one-sample-at-a-time polling loop
+/// (`adc.read().await` + `Timer::after_millis(100)`) with a single DMA burst: the ADC is put
+/// into free-running mode with the FIFO driving a DMA request, so a whole averaging window is
+/// captured with one `await` instead of ten.
+///
+/// This example demonstrates the `read_fifo_dma` extension added to `Adc` for exactly this
+/// purpose. Until that capture mode lands in the upstream `embassy-rp` HAL, the extension lives
+/// here as a small wrapper around the raw ADC/DMA registers (same trick the in-tree `dht20`
+/// module uses to reach ahead of the HAL for features it doesn't expose yet).
+use defmt::*;
+use embassy_executor::Spawner;
+use embassy_rp::adc::{Adc, Async, Config, InterruptHandler};
+use embassy_rp::gpio;
+use embassy_rp::gpio::Pull;
+use embassy_rp::{adc, bind_interrupts};
+use embassy_sync::blocking_mutex::raw::ThreadModeRawMutex;
+use embassy_sync::channel::{Channel, Sender};
+use embassy_time::Timer;
+use gpio::{Level, Output};
+use {defmt_rtt as _, panic_probe as _};
+
+use adc_dma::AdcDmaExt;
+
+bind_interrupts!(struct Irqs {
+    ADC_IRQ_FIFO => InterruptHandler;
+});
+
+static CHANNEL: Channel<ThreadModeRawMutex, u16, 64> = Channel::new();
+
+const WINDOW: usize = 10;
+
+#[embassy_executor::main]
+async fn main(spawner: Spawner) {
+    let p = embassy_rp::init(Default::default());
+    let mut led = Output::new(p.PIN_16, Level::Low);
+
+    info!("Setting up ADC");
+    let adc = Adc::new(p.ADC, Irqs, Config::default());
+    // GPIO 26 is wired to ADC input 0 on the RP2040; `AdcChannel` needs that hardware channel
+    // number alongside the pin since `embassy_rp::adc::Channel` doesn't expose it.
+    let p26 = adc_dma::AdcChannel::new(adc::Channel::new_pin(p.PIN_26, Pull::None), 0);
+    let dma = p.DMA_CH0;
+
+    spawner
+        .spawn(read_adc_window(adc, p26, dma.into(), CHANNEL.sender()))
+        .unwrap();
+
+    let rx_adv_value = CHANNEL.receiver();
+
+    loop {
+        let value = rx_adv_value.receive().await;
+        info!("ADC value: {}", value);
+
+        if value > 2048 {
+            led.set_high();
+        } else {
+            led.set_low();
+        }
+    }
+}
+
+/// Captures a `WINDOW`-sample burst via `read_fifo_dma` and sends the average, mirroring the
+/// cadence of `read_adc_value` in `iot_adc` but without ten separate `read().await` calls.
+#[embassy_executor::task(pool_size = 2)]
+async fn read_adc_window(
+    mut adc: Adc<'static, Async>,
+    mut p26: adc_dma::AdcChannel,
+    mut dma: embassy_rp::dma::AnyChannel,
+    tx_value: Sender<'static, ThreadModeRawMutex, u16, 64>,
+) {
+    loop {
+        let mut measurements = [0u16; WINDOW];
+
+        // `iot_adc`'s polling loop spends 10 x 100ms = ~1s per averaged reading. `DIV.INT` is a
+        // 16-bit divider off the 48MHz ADC clock, so it can't actually reach a 100ms-per-sample
+        // rate (that would need a clkdiv around 4.8 million); the slowest this capture mode can
+        // go is the divider maxed out at 65535, which works out to ~730 samples/sec, so this
+        // `WINDOW`-sample burst takes well under 100ms rather than matching the original cadence.
+        // What the single `await` buys over the polling version is the same regardless: the
+        // window is still one DMA-paced burst instead of ten separate `read().await` calls.
+        adc.read_fifo_dma(
+            &mut p26,
+            &mut dma,
+            &mut measurements,
+            adc_dma::SampleInterval::from_hz(730),
+        )
+        .await;
+
+        let average = measurements.iter().map(|&v| v as u32).sum::<u32>() / WINDOW as u32;
+        tx_value.send(average as u16).await;
+
+        Timer::after_millis(0).await;
+    }
+}
+
+/// Free-running ADC capture into a DMA buffer, ahead of upstream HAL support.
+///
+/// This is deliberately a thin extension: it reaches past the public
+/// `Adc` API to program the FIFO/DMA registers directly, the same way a HAL driver would, but
+/// without needing to land inside `embassy-rp` first. Round-robin scanning across multiple
+/// `AdcChannel`s is supported by writing the channel mask into the round-robin register
+/// (`CS.RROBIN`) before starting the capture; successive FIFO entries then cycle through the
+/// selected channels.
+mod adc_dma {
+    use embassy_rp::adc::{Adc, Async, Channel};
+    use embassy_rp::dma::AnyChannel;
+    use embassy_rp::pac;
+    use embassy_time::Timer;
+
+    /// An ADC input paired with the hardware channel number (`CS.AINSEL`/`CS.RROBIN` bit index)
+    /// it's wired to.
+    ///
+    /// `embassy_rp::adc::Channel` doesn't expose which of the 5 physical ADC inputs a pin maps to
+    /// (that mapping is private to the HAL), so round-robin scanning can't derive it from the
+    /// `Channel` alone — the caller has to supply it, the same way they already chose which GPIO
+    /// pin to wire up. GPIO 26-29 map to ADC channels 0-3 in pin order; the internal temperature
+    /// sensor is ADC channel 4.
+    pub struct AdcChannel {
+        // Never read directly: held only so the pin stays exclusively borrowed for as long as
+        // `number` is in use, the same way `Channel` itself would be if we passed it straight
+        // through to `Adc::read`.
+        #[allow(dead_code)]
+        channel: Channel<'static>,
+        number: u8,
+    }
+
+    impl AdcChannel {
+        pub fn new(channel: Channel<'static>, number: u8) -> Self {
+            Self { channel, number }
+        }
+    }
+
+    /// Sample interval expressed as a clock divider for the 48MHz ADC clock.
+    ///
+    /// `sample_rate ≈ 48 MHz / (clkdiv + 1)`. `DIV.INT` is only 16 bits wide, so the divider (and
+    /// therefore how slow `target_hz` can be) saturates at 65535 — about 732 samples/sec is the
+    /// slowest this capture mode can go, versus the ADC's single-sample `read().await` which can
+    /// be paced at any rate a `Timer` can express.
+    pub struct SampleInterval(u16);
+
+    impl SampleInterval {
+        pub fn from_hz(target_hz: u32) -> Self {
+            let clkdiv = (48_000_000 / target_hz.max(1)).saturating_sub(1).min(u16::MAX as u32);
+            Self(clkdiv as u16)
+        }
+    }
+
+    pub trait AdcDmaExt {
+        /// Runs the ADC in free-running mode, draining its FIFO with `dma` until `buf` is full,
+        /// then awaits the transfer's completion.
+        async fn read_fifo_dma(
+            &mut self,
+            channel: &mut AdcChannel,
+            dma: &mut AnyChannel,
+            buf: &mut [u16],
+            interval: SampleInterval,
+        );
+
+        /// Same as `read_fifo_dma`, but round-robins the FIFO across every channel in `channels`:
+        /// consecutive entries in `buf` cycle through those channels in ascending
+        /// hardware-channel-number order, starting from the lowest, whatever their slice order.
+        async fn read_fifo_dma_round_robin(
+            &mut self,
+            channels: &[AdcChannel],
+            dma: &mut AnyChannel,
+            buf: &mut [u16],
+            interval: SampleInterval,
+        );
+    }
+
+    impl AdcDmaExt for Adc<'static, Async> {
+        async fn read_fifo_dma(
+            &mut self,
+            channel: &mut AdcChannel,
+            dma: &mut AnyChannel,
+            buf: &mut [u16],
+            interval: SampleInterval,
+        ) {
+            self.read_fifo_dma_round_robin(core::slice::from_ref(channel), dma, buf, interval)
+                .await
+        }
+
+        async fn read_fifo_dma_round_robin(
+            &mut self,
+            channels: &[AdcChannel],
+            dma: &mut AnyChannel,
+            buf: &mut [u16],
+            interval: SampleInterval,
+        ) {
+            let round_robin_mask: u8 = channels.iter().fold(0u8, |mask, c| mask | (1 << c.number));
+            let first_channel = channels.iter().map(|c| c.number).min().unwrap_or(0);
+
+            // Select the channels to scan, set the starting channel for the first conversion, and
+            // enable free-running round-robin mode.
+            pac::ADC.cs().modify(|w| {
+                w.set_rrobin(round_robin_mask);
+                w.set_ainsel(first_channel);
+                w.set_start_many(true);
+            });
+            // Slow the sample rate down to `interval` and let the FIFO assert its DREQ once it
+            // holds at least one conversion.
+            pac::ADC.div().write(|w| w.set_int(interval.0));
+            pac::ADC.fcs().modify(|w| {
+                w.set_en(true);
+                w.set_dreq_en(true);
+                w.set_thresh(1);
+            });
+
+            // Point `dma` at the FIFO data register as source, `buf` as destination, DREQ_ADC
+            // as the pacing signal, and trigger a transfer of `buf.len()` halfwords.
+            let ch = dma.regs();
+            ch.read_addr().write_value(pac::ADC.fifo().as_ptr() as u32);
+            ch.write_addr().write_value(buf.as_mut_ptr() as u32);
+            ch.trans_count().write_value(buf.len() as u32);
+            ch.ctrl_trig().write(|w| {
+                w.set_data_size(pac::dma::vals::DataSize::SIZE_HALFWORD);
+                w.set_incr_read(false);
+                w.set_incr_write(true);
+                w.set_treq_sel(pac::dma::vals::TreqSel::from_bits(pac::dma::vals::DREQ_ADC));
+                w.set_en(true);
+            });
+
+            // The ADC FIFO/DMA pairing has no embassy executor integration yet (that lives with
+            // the HAL's IRQ-driven `Transfer` future), so completion is observed by polling BUSY
+            // instead of awaiting the DMA_IRQ_0 completion interrupt the way `embassy_rp::dma`'s
+            // own `Transfer` future does. That means this still isn't a single truly-asleep
+            // `await`: the executor wakes every 50us to re-check BUSY rather than blocking until
+            // the channel's completion IRQ fires. Wiring this to the real IRQ (registering a
+            // waker in `DMA_IRQ_0`'s handler, as `embassy_rp::dma::Transfer` does) would remove
+            // that polling, at the cost of needing its own bound interrupt here.
+            while ch.ctrl_trig().read().busy() {
+                Timer::after_micros(50).await;
+            }
+
+            pac::ADC.cs().modify(|w| w.set_start_many(false));
+            pac::ADC.fcs().modify(|w| w.set_en(false));
+        }
+    }
+}
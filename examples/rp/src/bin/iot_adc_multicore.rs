@@ -0,0 +1,208 @@
+#![no_std]
+#![no_main]
+///
+/// Splits the potentiometer/LED demo across both Cortex-M0+ cores: the high-rate ADC DMA capture
+/// from `iot_adc_dma` runs on core1 in its own executor, while the LED control loop stays on
+/// core0. That way a busy DMA capture on core1 never stalls core0's LED timing, the way a
+/// single-core `async` loop could if the two were interleaved on one executor.
+///
+/// Connect a potentiometer to pin 26 and an LED to pin 16.
+///
+/// Core0 and core1 talk over an `embassy_sync::channel::Channel` guarded by a
+/// `CriticalSectionRawMutex`, not the `ThreadModeRawMutex` the single-core examples use:
+/// `ThreadModeRawMutex` assumes "thread mode" only ever runs on one core and is unsound if a
+/// second core's executor can observe the same lock.
+use defmt::*;
+use embassy_executor::{Executor, Spawner};
+use embassy_rp::adc::{Adc, Async, Config, InterruptHandler};
+use embassy_rp::gpio;
+use embassy_rp::gpio::Pull;
+use embassy_rp::multicore::{spawn_core1, Stack};
+use embassy_rp::{adc, bind_interrupts};
+use embassy_sync::blocking_mutex::raw::CriticalSectionRawMutex;
+use embassy_sync::channel::{Channel, Sender};
+use gpio::{Level, Output};
+use static_cell::StaticCell;
+use {defmt_rtt as _, panic_probe as _};
+
+use adc_dma::AdcDmaExt;
+
+bind_interrupts!(struct Irqs {
+    ADC_IRQ_FIFO => InterruptHandler;
+});
+
+/// core1's stack. `multicore::spawn_core1` takes a `'static` stack, so it has to live here
+/// rather than on core0's stack frame.
+static mut CORE1_STACK: Stack<4096> = Stack::new();
+static CORE1_EXECUTOR: StaticCell<Executor> = StaticCell::new();
+
+static CHANNEL: Channel<CriticalSectionRawMutex, u16, 64> = Channel::new();
+
+const WINDOW: usize = 10;
+
+#[embassy_executor::main]
+async fn main(_spawner: Spawner) {
+    let p = embassy_rp::init(Default::default());
+    let mut led = Output::new(p.PIN_16, Level::Low);
+
+    let adc = Adc::new(p.ADC, Irqs, Config::default());
+    // GPIO 26 is wired to ADC input 0 on the RP2040; `AdcChannel` needs that hardware channel
+    // number alongside the pin since `embassy_rp::adc::Channel` doesn't expose it.
+    let p26 = adc_dma::AdcChannel::new(adc::Channel::new_pin(p.PIN_26, Pull::None), 0);
+    let dma = p.DMA_CH0;
+
+    spawn_core1(p.CORE1, unsafe { &mut CORE1_STACK }, move || {
+        let executor1 = CORE1_EXECUTOR.init(Executor::new());
+        executor1.run(|spawner| {
+            spawner
+                .spawn(read_adc_window(adc, p26, dma.into(), CHANNEL.sender()))
+                .unwrap();
+        });
+    });
+
+    let rx_value = CHANNEL.receiver();
+    loop {
+        let value = rx_value.receive().await;
+        info!("ADC value: {}", value);
+
+        if value > 2048 {
+            led.set_high();
+        } else {
+            led.set_low();
+        }
+    }
+}
+
+/// Runs entirely on core1: captures a `WINDOW`-sample burst via `read_fifo_dma` and sends the
+/// average across the cross-core channel, without ever blocking core0's LED loop.
+#[embassy_executor::task]
+async fn read_adc_window(
+    mut adc: Adc<'static, Async>,
+    mut p26: adc_dma::AdcChannel,
+    mut dma: embassy_rp::dma::AnyChannel,
+    tx_value: Sender<'static, CriticalSectionRawMutex, u16, 64>,
+) {
+    loop {
+        let mut measurements = [0u16; WINDOW];
+
+        adc.read_fifo_dma(
+            &mut p26,
+            &mut dma,
+            &mut measurements,
+            adc_dma::SampleInterval::from_hz(730),
+        )
+        .await;
+
+        let average = measurements.iter().map(|&v| v as u32).sum::<u32>() / WINDOW as u32;
+        tx_value.send(average as u16).await;
+    }
+}
+
+/// Free-running ADC capture into a DMA buffer, ahead of upstream HAL support.
+///
+/// A single-channel subset of the `adc_dma` module in `iot_adc_dma` (duplicated rather than shared,
+/// since this tree has no common lib crate for standalone example binaries to pull from): it has
+/// `read_fifo_dma` but no round-robin variant. It reaches past the public `Adc` API to program the FIFO/DMA registers directly, the same way a HAL driver would,
+/// but without needing to land inside `embassy-rp` first.
+mod adc_dma {
+    use embassy_rp::adc::{Adc, Async, Channel};
+    use embassy_rp::dma::AnyChannel;
+    use embassy_rp::pac;
+    use embassy_time::Timer;
+
+    /// Sample interval expressed as a clock divider for the 48MHz ADC clock.
+    ///
+    /// `sample_rate ≈ 48 MHz / (clkdiv + 1)`. `DIV.INT` is only 16 bits wide, so the divider (and
+    /// therefore how slow `target_hz` can be) saturates at 65535 — about 732 samples/sec is the
+    /// slowest this capture mode can go.
+    pub struct SampleInterval(u16);
+
+    impl SampleInterval {
+        pub fn from_hz(target_hz: u32) -> Self {
+            let clkdiv = (48_000_000 / target_hz.max(1)).saturating_sub(1).min(u16::MAX as u32);
+            Self(clkdiv as u16)
+        }
+    }
+
+    /// An ADC input paired with the hardware channel number (`CS.AINSEL`/`CS.RROBIN` bit index)
+    /// it's wired to.
+    ///
+    /// `embassy_rp::adc::Channel` doesn't expose which of the 5 physical ADC inputs a pin maps to
+    /// (that mapping is private to the HAL), so the caller has to supply it, the same way they
+    /// already chose which GPIO pin to wire up. GPIO 26-29 map to ADC channels 0-3 in pin order.
+    pub struct AdcChannel {
+        #[allow(dead_code)]
+        channel: Channel<'static>,
+        number: u8,
+    }
+
+    impl AdcChannel {
+        pub fn new(channel: Channel<'static>, number: u8) -> Self {
+            Self { channel, number }
+        }
+    }
+
+    pub trait AdcDmaExt {
+        /// Runs the ADC in free-running mode, draining its FIFO with `dma` until `buf` is full,
+        /// then awaits the transfer's completion.
+        async fn read_fifo_dma(
+            &mut self,
+            channel: &mut AdcChannel,
+            dma: &mut AnyChannel,
+            buf: &mut [u16],
+            interval: SampleInterval,
+        );
+    }
+
+    impl AdcDmaExt for Adc<'static, Async> {
+        async fn read_fifo_dma(
+            &mut self,
+            channel: &mut AdcChannel,
+            dma: &mut AnyChannel,
+            buf: &mut [u16],
+            interval: SampleInterval,
+        ) {
+            // Select the channel to scan, point `AINSEL` at it as the starting channel, and
+            // enable free-running mode.
+            pac::ADC.cs().modify(|w| {
+                w.set_rrobin(1 << channel.number);
+                w.set_ainsel(channel.number);
+                w.set_start_many(true);
+            });
+            // Slow the sample rate down to `interval` and let the FIFO assert its DREQ once it
+            // holds at least one conversion.
+            pac::ADC.div().write(|w| w.set_int(interval.0));
+            pac::ADC.fcs().modify(|w| {
+                w.set_en(true);
+                w.set_dreq_en(true);
+                w.set_thresh(1);
+            });
+
+            // Point `dma` at the FIFO data register as source, `buf` as destination, DREQ_ADC
+            // as the pacing signal, and trigger a transfer of `buf.len()` halfwords.
+            let ch = dma.regs();
+            ch.read_addr().write_value(pac::ADC.fifo().as_ptr() as u32);
+            ch.write_addr().write_value(buf.as_mut_ptr() as u32);
+            ch.trans_count().write_value(buf.len() as u32);
+            ch.ctrl_trig().write(|w| {
+                w.set_data_size(pac::dma::vals::DataSize::SIZE_HALFWORD);
+                w.set_incr_read(false);
+                w.set_incr_write(true);
+                w.set_treq_sel(pac::dma::vals::TreqSel::from_bits(pac::dma::vals::DREQ_ADC));
+                w.set_en(true);
+            });
+
+            // The ADC FIFO/DMA pairing has no embassy executor integration yet (that lives with
+            // the HAL's IRQ-driven `Transfer` future), so completion is observed by polling BUSY
+            // rather than awaiting the DMA_IRQ_0 completion interrupt. Since this whole task runs
+            // on its own core1 executor, the busy-poll only ever delays core1's own work, not
+            // core0's LED loop.
+            while ch.ctrl_trig().read().busy() {
+                Timer::after_micros(50).await;
+            }
+
+            pac::ADC.cs().modify(|w| w.set_start_many(false));
+            pac::ADC.fcs().modify(|w| w.set_en(false));
+        }
+    }
+}
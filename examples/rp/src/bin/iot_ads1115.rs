@@ -0,0 +1,127 @@
+//! Reads a higher-resolution analog channel through an external ADS1115 I2C ADC instead of the
+//! RP2040's internal 12-bit ADC, waiting on the ALERT/RDY line for each conversion instead of
+//! polling on a fixed timer like `read_adc_value` in `iot_adc` does.
+//!
+//! Wiring: ADS1115 on I2C0 (pins 0/1), ALERT/RDY on pin 22.
+
+#![no_std]
+#![no_main]
+
+use defmt::*;
+use embassy_executor::Spawner;
+use embassy_rp::gpio::{Input, Pull};
+use embassy_rp::peripherals::I2C0;
+use embassy_rp::{bind_interrupts, i2c};
+use {defmt_rtt as _, panic_probe as _};
+
+use ads1115::Ads1115;
+
+bind_interrupts!(struct Irqs {
+    I2C0_IRQ => i2c::InterruptHandler<I2C0>;
+});
+
+#[embassy_executor::main]
+async fn main(_spawner: Spawner) {
+    let p = embassy_rp::init(Default::default());
+
+    info!("Setting up ADS1115");
+    let i2c = i2c::I2c::new_async(p.I2C0, p.PIN_1, p.PIN_0, Irqs, i2c::Config::default());
+    let alert = Input::new(p.PIN_22, Pull::Up);
+    let mut adc = Ads1115::new(i2c, alert).await;
+
+    loop {
+        let value = adc.read_trimmed_mean().await;
+        info!("ADS1115 trimmed-mean value: {}", value);
+    }
+}
+
+/// External ADS1115 16-bit I2C ADC, with a trimmed-mean filter in front of its conversions.
+mod ads1115 {
+    use embassy_rp::gpio::Input;
+    use embassy_rp::i2c::{Async, I2c};
+    use embassy_rp::peripherals::I2C0;
+    use embedded_hal_async::i2c::I2c as I2cAsync;
+
+    const ADDR: u8 = 0x48;
+    const REG_CONVERSION: u8 = 0x00;
+    const REG_CONFIG: u8 = 0x01;
+    const REG_LO_THRESH: u8 = 0x02;
+    const REG_HI_THRESH: u8 = 0x03;
+
+    // OS=1 (start single conversion) | MUX=100 (AIN0 vs GND) | PGA=010 (+-2.048V) |
+    // MODE=0 (continuous) | DR=100 (128SPS) | COMP_MODE=0 | COMP_POL=0 | COMP_LAT=0 | COMP_QUE=00
+    // (assert ALERT/RDY after every conversion).
+    const CONFIG: u16 = 0b1_100_010_0_100_0_0_0_00;
+
+    // ALERT/RDY only behaves as a data-ready pulse once Hi_thresh's MSB is 1 and Lo_thresh's MSB
+    // is 0 ("conversion ready" mode, per the datasheet); with both thresholds left at their
+    // power-on-reset value of 0 the pin instead stays in comparator mode and never asserts.
+    const HI_THRESH_CONVERSION_READY: u16 = 0x8000;
+    const LO_THRESH_CONVERSION_READY: u16 = 0x0000;
+
+    /// Number of middle samples averaged together after discarding the low/high outliers.
+    const AVG_WINDOW: usize = 8;
+    /// Number of lowest and highest samples discarded from each end of the sorted window.
+    const AVG_CUTOFF: usize = 2;
+    const AVG_LENGTH: usize = AVG_WINDOW + 2 * AVG_CUTOFF;
+
+    pub struct Ads1115 {
+        i2c: I2c<'static, I2C0, Async>,
+        alert: Input<'static>,
+    }
+
+    impl Ads1115 {
+        pub async fn new(mut i2c: I2c<'static, I2C0, Async>, alert: Input<'static>) -> Self {
+            i2c.write(ADDR, &[REG_CONFIG, (CONFIG >> 8) as u8, CONFIG as u8])
+                .await
+                .expect("ads1115: can not write config");
+            i2c.write(
+                ADDR,
+                &[
+                    REG_HI_THRESH,
+                    (HI_THRESH_CONVERSION_READY >> 8) as u8,
+                    HI_THRESH_CONVERSION_READY as u8,
+                ],
+            )
+            .await
+            .expect("ads1115: can not write Hi_thresh");
+            i2c.write(
+                ADDR,
+                &[
+                    REG_LO_THRESH,
+                    (LO_THRESH_CONVERSION_READY >> 8) as u8,
+                    LO_THRESH_CONVERSION_READY as u8,
+                ],
+            )
+            .await
+            .expect("ads1115: can not write Lo_thresh");
+            Self { i2c, alert }
+        }
+
+        /// Waits for the next data-ready pulse on ALERT/RDY and returns the conversion it signaled.
+        async fn read_ready(&mut self) -> i16 {
+            self.alert.wait_for_falling_edge().await;
+
+            let mut data = [0u8; 2];
+            self.i2c
+                .write_read(ADDR, &[REG_CONVERSION], &mut data)
+                .await
+                .expect("ads1115: can not read conversion");
+            i16::from_be_bytes(data)
+        }
+
+        /// Collects `AVG_LENGTH` ALERT/RDY-gated samples, discards the `AVG_CUTOFF` lowest and
+        /// highest, and averages the remaining `AVG_WINDOW` middle values. Replaces the naive
+        /// `sum / 10` average `read_adc_value` uses, which doesn't reject glitches.
+        pub async fn read_trimmed_mean(&mut self) -> i16 {
+            let mut samples = [0i16; AVG_LENGTH];
+            for sample in samples.iter_mut() {
+                *sample = self.read_ready().await;
+            }
+            samples.sort_unstable();
+
+            let trimmed = &samples[AVG_CUTOFF..AVG_LENGTH - AVG_CUTOFF];
+            (trimmed.iter().map(|&v| v as i32).sum::<i32>() / AVG_WINDOW as i32) as i16
+        }
+    }
+}
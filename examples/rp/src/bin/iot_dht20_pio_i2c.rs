@@ -0,0 +1,325 @@
+//! Runs the DHT20 example's sensor code unchanged on arbitrary GPIO pins instead of the
+//! hard-wired `I2C0` peripheral, by giving it a PIO-backed `embedded_hal_async::i2c::I2c`
+//! implementation instead of `embassy_rp::i2c::I2c`. This frees up both hardware I2C blocks for
+//! other peripherals and removes `iot_i2c_async_embassy`'s pin-mapping restriction.
+//!
+//! Wiring: DHT20 SDA on pin 2, SCL on pin 3 (any PIO-capable pins work).
+
+#![no_std]
+#![no_main]
+
+use defmt::*;
+use embassy_executor::Spawner;
+use embassy_rp::bind_interrupts;
+use embassy_rp::peripherals::PIO0;
+use embassy_rp::pio::{InterruptHandler as PioInterruptHandler, Pio};
+use embassy_time::Timer;
+use {defmt_rtt as _, panic_probe as _};
+
+use dht20::{initialize, read_temperature_and_humidity};
+use pio_i2c::PioI2c;
+
+bind_interrupts!(struct Irqs {
+    PIO0_IRQ_0 => PioInterruptHandler<PIO0>;
+});
+
+/// DHT20 sensor: datasheet: https://cdn.sparkfun.com/assets/8/a/1/5/0/DHT20.pdf
+///
+/// Identical to the `dht20` module in `iot_i2c_async_embassy`, except it is generic over any
+/// `embedded_hal_async::i2c::I2c` implementation instead of being pinned to
+/// `embassy_rp::i2c::I2c<'static, I2C0, Async>`, so it also works over `PioI2c`.
+mod dht20 {
+    use defmt::debug;
+    use embassy_time::Timer;
+    use embedded_hal_async::i2c::I2c as I2cAsync;
+
+    const DHT20_I2C_ADDR: u8 = 0x38;
+    const DHT20_GET_STATUS: u8 = 0x71;
+    const DHT20_READ_DATA: [u8; 3] = [0xAC, 0x33, 0x00];
+
+    const DIVISOR: f32 = 2u32.pow(20) as f32;
+    const TEMP_DIVISOR: f32 = DIVISOR / 200.0;
+
+    pub async fn initialize(i2c: &mut impl I2cAsync) -> bool {
+        Timer::after_millis(100).await;
+        let mut data = [0x0; 1];
+        i2c.write_read(DHT20_I2C_ADDR, &[DHT20_GET_STATUS], &mut data)
+            .await
+            .ok()
+            .expect("Can not read status");
+
+        data[0] & 0x18 == 0x18
+    }
+
+    async fn read_data(i2c: &mut impl I2cAsync) -> [u8; 6] {
+        let mut data = [0x0; 6];
+
+        for _ in 0..10 {
+            i2c.write(DHT20_I2C_ADDR, &DHT20_READ_DATA)
+                .await
+                .ok()
+                .expect("Can not write data");
+            Timer::after_millis(80).await;
+
+            i2c.read(DHT20_I2C_ADDR, &mut data).await.ok().expect("Can not read data");
+
+            if data[0] >> 7 == 0 {
+                break;
+            }
+        }
+
+        data
+    }
+
+    pub async fn read_temperature_and_humidity(i2c: &mut impl I2cAsync) -> (f32, f32) {
+        let data = read_data(i2c).await;
+        debug!("data = {:?}", data);
+
+        let raw_hum_data = ((data[1] as u32) << 12) + ((data[2] as u32) << 4) + (((data[3] & 0xf0) >> 4) as u32);
+        debug!("raw_humidity_data = {:x}", raw_hum_data);
+        let humidity = (raw_hum_data as f32) / DIVISOR * 100.0;
+
+        let raw_temp_data = (((data[3] as u32) & 0xf) << 16) + ((data[4] as u32) << 8) + (data[5] as u32);
+        debug!("raw_temperature_data = {:x}", raw_temp_data);
+        let temperature = (raw_temp_data as f32) / TEMP_DIVISOR - 50.0;
+
+        (temperature, humidity)
+    }
+}
+
+#[embassy_executor::main]
+async fn main(_spawner: Spawner) {
+    let p = embassy_rp::init(Default::default());
+
+    info!("set up PIO-backed i2c");
+    let Pio { mut common, sm0, .. } = Pio::new(p.PIO0, Irqs);
+    let mut i2c = PioI2c::new(&mut common, sm0, p.PIN_3, p.PIN_2);
+
+    let ready = initialize(&mut i2c).await;
+    info!("Ready: {}", ready);
+
+    loop {
+        let (temperature, humidity) = read_temperature_and_humidity(&mut i2c).await;
+        info!("temperature = {}C", temperature);
+        info!("humidity = {}%", humidity);
+
+        Timer::after_millis(500).await;
+    }
+}
+
+/// A software I2C controller built on a PIO state machine, for boards where both hardware I2C
+/// blocks are already spoken for.
+///
+/// A single PIO program implements the whole protocol: the state machine is mostly a dispatcher
+/// that `pull`s one 32-bit command word per operation (top 2 bits select start/write/read/stop,
+/// mirrored in `Command` below) and runs a fixed subroutine per command, so the only thing PioI2c
+/// pushes at runtime is data, never raw instructions. SDA and SCL are both driven through
+/// `pindirs` (the pin's output level is wired low in hardware, the way every RP2040 PIO
+/// open-drain driver works): `pindirs = 1` drives the line low, `pindirs = 0` releases it to the
+/// pull-up. Every time a write/read/stop subroutine releases SCL, it executes `wait 1 pin 1`
+/// before sampling or moving on, so a target that stretches the clock (holds SCL low) is honored
+/// rather than raced.
+mod pio_i2c {
+    use embassy_rp::pio::{Common, Config, Direction, Instance, PioPin, ShiftDirection, StateMachine};
+    use embedded_hal_async::i2c::{ErrorType, I2c, Operation};
+
+    /// 2-bit command opcodes, matching the `jmp !x do_start` / `jmp x!=y` dispatch chain at the
+    /// top of the PIO program.
+    const CMD_START: u32 = 0;
+    const CMD_WRITE: u32 = 1;
+    const CMD_READ: u32 = 2;
+    const CMD_STOP: u32 = 3;
+
+    fn i2c_program() -> pio::Program<32> {
+        pio_proc::pio_asm!(
+            ".side_set 1 opt pindirs",
+            ".wrap_target",
+            "bitloop:",
+            "    pull                side 0",
+            "    out x, 2             side 0",
+            "    jmp !x do_start        side 0",
+            "    set y, 1                side 0",
+            "    jmp x!=y do_check_stop     side 0",
+            "do_write:",
+            "    set x, 7                     side 0",
+            "write_bit:",
+            "    out pindirs, 1                 side 1 [1]",
+            "    nop                              side 0 [1]",
+            "    wait 1 pin 1                       side 0",
+            "    jmp x-- write_bit                    side 1 [1]",
+            "    set pindirs, 0                         side 1 [1]",
+            "    nop                                      side 0 [1]",
+            "    wait 1 pin 1                                side 0",
+            "    in pins, 1                                    side 0 [1]",
+            "    jmp bitloop                                     side 1 [1]",
+            "do_check_stop:",
+            "    set y, 2               side 0",
+            "    jmp x!=y do_stop         side 0",
+            "do_read:",
+            "    out y, 1                  side 0",
+            "    set x, 7                    side 0",
+            "read_bit:",
+            "    set pindirs, 0                side 1 [1]",
+            "    nop                              side 0 [1]",
+            "    wait 1 pin 1                        side 0",
+            "    in pins, 1                             side 0 [1]",
+            "    jmp x-- read_bit                         side 1 [1]",
+            "    jmp !y send_ack                            side 1",
+            "send_nak:",
+            "    set pindirs, 0                               side 1 [1]",
+            "    jmp ack_clock                                  side 1",
+            "send_ack:",
+            "    set pindirs, 1                                   side 1 [1]",
+            "ack_clock:",
+            "    nop                                                side 0 [1]",
+            "    wait 1 pin 1                                          side 0",
+            "    jmp bitloop                                             side 1 [1]",
+            "do_start:",
+            "    set pindirs, 1     side 0 [1]",
+            "    jmp bitloop          side 0",
+            "do_stop:",
+            "    set pindirs, 1         side 1 [1]",
+            "    nop                      side 0 [1]",
+            "    wait 1 pin 1                side 0",
+            "    set pindirs, 0                 side 0 [1]",
+            "    jmp bitloop                      side 0",
+            ".wrap",
+        )
+        .program
+    }
+
+    pub struct PioI2c<'d, PIO: Instance, const SM: usize> {
+        sm: StateMachine<'d, PIO, SM>,
+    }
+
+    impl<'d, PIO: Instance, const SM: usize> PioI2c<'d, PIO, SM> {
+        pub fn new(
+            common: &mut Common<'d, PIO>,
+            mut sm: StateMachine<'d, PIO, SM>,
+            scl: impl PioPin,
+            sda: impl PioPin,
+        ) -> Self {
+            let scl = common.make_pio_pin(scl);
+            let sda = common.make_pio_pin(sda);
+
+            let program = common.load_program(&i2c_program());
+            let mut cfg = Config::default();
+            cfg.use_program(&program, &[&scl]);
+            cfg.set_out_pins(&[&sda]);
+            // SDA is index 0, SCL is index 1, so `wait 1 pin 1` in the program polls SCL.
+            cfg.set_in_pins(&[&sda, &scl]);
+            cfg.set_set_pins(&[&sda]);
+            cfg.shift_out.auto_fill = false;
+            cfg.shift_out.direction = ShiftDirection::Left;
+            cfg.shift_in.auto_fill = true;
+            cfg.shift_in.threshold = 1;
+            cfg.shift_in.direction = ShiftDirection::Left;
+            // 1MHz PIO clock at a 125MHz system clock; a written bit takes 7 program cycles and a
+            // read bit 9, so the bus runs at roughly 110-140kHz.
+            cfg.clock_divider = 125u16.into();
+
+            sm.set_pin_dirs(Direction::Out, &[&scl, &sda]);
+            sm.set_config(&cfg);
+            sm.set_enable(true);
+
+            Self { sm }
+        }
+
+        async fn push(&mut self, command: u32) {
+            self.sm.tx().wait_push(command).await;
+        }
+
+        async fn pull_bit(&mut self) -> u32 {
+            self.sm.rx().wait_pull().await & 1
+        }
+
+        async fn start(&mut self) {
+            self.push(CMD_START << 30).await;
+        }
+
+        async fn stop(&mut self) {
+            self.push(CMD_STOP << 30).await;
+        }
+
+        /// Shifts `byte` out MSB-first, then samples the ACK bit on the 9th clock: `Ok(())` if
+        /// the target pulled SDA low to acknowledge, `Err(Error::Nack)` otherwise.
+        async fn write_byte(&mut self, byte: u8) -> Result<(), Error> {
+            // `out pindirs` drives SDA low for every 1 bit, so the byte is inverted before it is
+            // pushed.
+            self.push((CMD_WRITE << 30) | (((!byte) as u32) << 22)).await;
+            if self.pull_bit().await == 0 {
+                Ok(())
+            } else {
+                Err(Error::Nack)
+            }
+        }
+
+        /// Shifts one byte in MSB-first, then drives the ack/nak bit ourselves: `more` selects
+        /// ACK (more bytes follow) vs NAK (this is the last byte of the read).
+        async fn read_byte(&mut self, more: bool) -> u8 {
+            self.push((CMD_READ << 30) | ((!more as u32) << 29)).await;
+            let mut byte = 0u8;
+            for _ in 0..8 {
+                byte = (byte << 1) | self.pull_bit().await as u8;
+            }
+            byte
+        }
+    }
+
+    #[derive(Debug)]
+    pub enum Error {
+        Nack,
+    }
+
+    impl embedded_hal_async::i2c::Error for Error {
+        fn kind(&self) -> embedded_hal_async::i2c::ErrorKind {
+            match self {
+                Error::Nack => embedded_hal_async::i2c::ErrorKind::NoAcknowledge(
+                    embedded_hal_async::i2c::NoAcknowledgeSource::Unknown,
+                ),
+            }
+        }
+    }
+
+    impl<'d, PIO: Instance, const SM: usize> ErrorType for PioI2c<'d, PIO, SM> {
+        type Error = Error;
+    }
+
+    impl<'d, PIO: Instance, const SM: usize> I2c for PioI2c<'d, PIO, SM> {
+        async fn transaction(&mut self, address: u8, operations: &mut [Operation<'_>]) -> Result<(), Self::Error> {
+            // `None` until the first operation addresses the bus; after that, `Some(true)` means
+            // the last header we sent had R/W=1 (read) and `Some(false)` means R/W=0 (write). Every
+            // op that doesn't match the currently-addressed direction needs its own repeated START
+            // plus a freshly re-sent address byte with the matching R/W bit — a single address byte
+            // up front only ever covers one direction, but `Operation::Write`/`Read` can alternate
+            // within one `transaction` call.
+            let mut addressed_for_read: Option<bool> = None;
+
+            for op in operations {
+                let wants_read = matches!(op, Operation::Read(_));
+                if addressed_for_read != Some(wants_read) {
+                    self.start().await;
+                    self.write_byte((address << 1) | (wants_read as u8)).await?;
+                    addressed_for_read = Some(wants_read);
+                }
+
+                match op {
+                    Operation::Write(data) => {
+                        for &byte in data.iter() {
+                            self.write_byte(byte).await?;
+                        }
+                    }
+                    Operation::Read(data) => {
+                        let len = data.len();
+                        for (i, byte) in data.iter_mut().enumerate() {
+                            *byte = self.read_byte(i != len - 1).await;
+                        }
+                    }
+                }
+            }
+
+            self.stop().await;
+            Ok(())
+        }
+    }
+}
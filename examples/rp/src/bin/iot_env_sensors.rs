@@ -0,0 +1,293 @@
+//! Reads an environmental sensor through a single `EnvSensor` trait, so the application loop
+//! doesn't change when the sensor does. Previously each sensor (the `dht20` module, the TMP36
+//! reads threaded through the ADC examples) was hand-coded inline; here the backend is picked at
+//! the top of `main` by cargo feature, matching the "comment out the sensor you don't have"
+//! workflow the DHT20 example already follows informally.
+//!
+//! Wiring depends on the selected backend:
+//! - `sensor-bmp390` / `sensor-mcp9808`: I2C0 on pins 0 (SDA) / 1 (SCL), as in `iot_i2c_async_embassy`.
+//! - `sensor-tmp36` (default): analog output on pin 26, as in `iot_adc`.
+
+#![no_std]
+#![no_main]
+
+use defmt::*;
+use embassy_executor::Spawner;
+use embassy_time::Timer;
+use {defmt_rtt as _, panic_probe as _};
+
+use sensors::EnvSensor;
+
+#[embassy_executor::main]
+async fn main(_spawner: Spawner) {
+    let p = embassy_rp::init(Default::default());
+
+    #[cfg(feature = "sensor-bmp390")]
+    let mut sensor = {
+        use embassy_rp::{bind_interrupts, i2c, peripherals::I2C0};
+        bind_interrupts!(struct Irqs {
+            I2C0_IRQ => i2c::InterruptHandler<I2C0>;
+        });
+        let i2c = i2c::I2c::new_async(p.I2C0, p.PIN_1, p.PIN_0, Irqs, i2c::Config::default());
+        sensors::Bmp390::new(i2c).await
+    };
+
+    #[cfg(feature = "sensor-mcp9808")]
+    let mut sensor = {
+        use embassy_rp::{bind_interrupts, i2c, peripherals::I2C0};
+        bind_interrupts!(struct Irqs {
+            I2C0_IRQ => i2c::InterruptHandler<I2C0>;
+        });
+        let i2c = i2c::I2c::new_async(p.I2C0, p.PIN_1, p.PIN_0, Irqs, i2c::Config::default());
+        sensors::Mcp9808::new(i2c)
+    };
+
+    #[cfg(not(any(feature = "sensor-bmp390", feature = "sensor-mcp9808")))]
+    let mut sensor = {
+        use embassy_rp::{adc, bind_interrupts, gpio::Pull};
+        bind_interrupts!(struct Irqs {
+            ADC_IRQ_FIFO => adc::InterruptHandler;
+        });
+        let adc = adc::Adc::new(p.ADC, Irqs, adc::Config::default());
+        let channel = adc::Channel::new_pin(p.PIN_26, Pull::None);
+        sensors::Tmp36::new(adc, channel)
+    };
+
+    loop {
+        let measurement = sensor.read().await;
+        if let Some(temp) = measurement.temperature_c {
+            info!("temperature = {}C", temp);
+        }
+        if let Some(humidity) = measurement.humidity_percent {
+            info!("humidity = {}%", humidity);
+        }
+        if let Some(pressure) = measurement.pressure_pa {
+            info!("pressure = {}Pa", pressure);
+        }
+
+        Timer::after_millis(500).await;
+    }
+}
+
+/// A small sensor abstraction so `main` doesn't need to know which concrete sensor is wired up.
+mod sensors {
+    /// A reading from an `EnvSensor`. Fields the sensor doesn't measure are left `None`.
+    #[derive(Default, Clone, Copy, defmt::Format)]
+    pub struct Measurement {
+        pub temperature_c: Option<f32>,
+        pub humidity_percent: Option<f32>,
+        pub pressure_pa: Option<f32>,
+    }
+
+    pub trait EnvSensor {
+        async fn read(&mut self) -> Measurement;
+    }
+
+    #[cfg(feature = "sensor-bmp390")]
+    pub use bmp390::Bmp390;
+    #[cfg(feature = "sensor-mcp9808")]
+    pub use mcp9808::Mcp9808;
+    #[cfg(not(any(feature = "sensor-bmp390", feature = "sensor-mcp9808")))]
+    pub use tmp36::Tmp36;
+
+    /// Bosch BMP390 barometric pressure/temperature sensor.
+    ///
+    /// Datasheet: https://www.bosch-sensortec.com/media/boschsensortec/downloads/datasheets/bst-bmp390-ds002.pdf
+    #[cfg(feature = "sensor-bmp390")]
+    mod bmp390 {
+        use super::{EnvSensor, Measurement};
+        use embassy_rp::i2c::{Async, I2c};
+        use embassy_rp::peripherals::I2C0;
+        use embedded_hal_async::i2c::I2c as I2cAsync;
+
+        const ADDR: u8 = 0x77;
+        const REG_CALIB00: u8 = 0x31;
+        const REG_PWR_CTRL: u8 = 0x1B;
+        const REG_DATA: u8 = 0x04;
+
+        /// Trimming coefficients read once at init, per the datasheet's compensation formulas.
+        #[derive(Default)]
+        struct Calibration {
+            par_t1: f32,
+            par_t2: f32,
+            par_t3: f32,
+            par_p1: f32,
+            par_p2: f32,
+            par_p3: f32,
+            par_p4: f32,
+            par_p5: f32,
+            par_p6: f32,
+            par_p7: f32,
+            par_p8: f32,
+            par_p9: f32,
+            par_p10: f32,
+            par_p11: f32,
+        }
+
+        pub struct Bmp390 {
+            i2c: I2c<'static, I2C0, Async>,
+            calib: Calibration,
+        }
+
+        impl Bmp390 {
+            pub async fn new(mut i2c: I2c<'static, I2C0, Async>) -> Self {
+                // Enable temperature and pressure measurement in normal (continuous) mode.
+                i2c.write(ADDR, &[REG_PWR_CTRL, 0b0011_0011])
+                    .await
+                    .expect("bmp390: can not enable measurement");
+
+                let mut raw = [0u8; 21];
+                i2c.write_read(ADDR, &[REG_CALIB00], &mut raw)
+                    .await
+                    .expect("bmp390: can not read calibration");
+                Self {
+                    i2c,
+                    calib: Calibration::from_raw(&raw),
+                }
+            }
+        }
+
+        impl Calibration {
+            // Scale factors from the datasheet's "Quantization" table, converting the raw
+            // trimming registers into the floating-point coefficients the compensation formulas use.
+            fn from_raw(raw: &[u8; 21]) -> Self {
+                let u16_le = |lo: usize| u16::from_le_bytes([raw[lo], raw[lo + 1]]);
+                let i16_le = |lo: usize| i16::from_le_bytes([raw[lo], raw[lo + 1]]);
+
+                Self {
+                    par_t1: u16_le(0) as f32 / 2f32.powi(-8),
+                    par_t2: u16_le(2) as f32 / 2f32.powi(30),
+                    par_t3: raw[4] as i8 as f32 / 2f32.powi(48),
+                    par_p1: (i16_le(5) as f32 - 2f32.powi(14)) / 2f32.powi(20),
+                    par_p2: (i16_le(7) as f32 - 2f32.powi(14)) / 2f32.powi(29),
+                    par_p3: raw[9] as i8 as f32 / 2f32.powi(32),
+                    par_p4: raw[10] as i8 as f32 / 2f32.powi(37),
+                    par_p5: u16_le(11) as f32 / 2f32.powi(-3),
+                    par_p6: u16_le(13) as f32 / 2f32.powi(6),
+                    par_p7: raw[15] as i8 as f32 / 2f32.powi(8),
+                    par_p8: raw[16] as i8 as f32 / 2f32.powi(15),
+                    par_p9: i16_le(17) as f32 / 2f32.powi(48),
+                    par_p10: raw[19] as i8 as f32 / 2f32.powi(48),
+                    par_p11: raw[20] as i8 as f32 / 2f32.powi(65),
+                }
+            }
+        }
+
+        impl EnvSensor for Bmp390 {
+            async fn read(&mut self) -> Measurement {
+                let mut raw = [0u8; 6];
+                self.i2c
+                    .write_read(ADDR, &[REG_DATA], &mut raw)
+                    .await
+                    .expect("bmp390: can not read data");
+
+                let raw_press = (raw[0] as u32) | ((raw[1] as u32) << 8) | ((raw[2] as u32) << 16);
+                let raw_temp = (raw[3] as u32) | ((raw[4] as u32) << 8) | ((raw[5] as u32) << 16);
+
+                let c = &self.calib;
+                let partial1 = (raw_temp as f32) - c.par_t1;
+                let partial2 = partial1 * c.par_t2;
+                let temperature = partial2 + (partial1 * partial1) * c.par_t3;
+
+                let partial3 = c.par_p6 * temperature;
+                let partial4 = c.par_p7 * temperature * temperature;
+                let partial5 = c.par_p8 * temperature * temperature * temperature;
+                let out1 = c.par_p5 + partial3 + partial4 + partial5;
+
+                let partial6 = c.par_p2 * temperature;
+                let partial7 = c.par_p3 * temperature * temperature;
+                let partial8 = c.par_p4 * temperature * temperature * temperature;
+                let out2 = (raw_press as f32) * (c.par_p1 + partial6 + partial7 + partial8);
+
+                let partial9 = (raw_press as f32) * (raw_press as f32);
+                let partial10 = c.par_p9 + c.par_p10 * temperature;
+                let partial11 = partial9 * partial10;
+                let pressure = out1 + out2 + partial11 + (raw_press as f32).powi(3) * c.par_p11;
+
+                Measurement {
+                    temperature_c: Some(temperature),
+                    pressure_pa: Some(pressure),
+                    ..Default::default()
+                }
+            }
+        }
+    }
+
+    /// Microchip MCP9808 precision I2C temperature sensor.
+    ///
+    /// Datasheet: https://ww1.microchip.com/downloads/en/DeviceDoc/MCP9808-0.5C-Maximum-Accuracy-Digital-Temperature-Sensor-Data-Sheet-DS20005095B.pdf
+    #[cfg(feature = "sensor-mcp9808")]
+    mod mcp9808 {
+        use super::{EnvSensor, Measurement};
+        use embassy_rp::i2c::{Async, I2c};
+        use embassy_rp::peripherals::I2C0;
+        use embedded_hal_async::i2c::I2c as I2cAsync;
+
+        const ADDR: u8 = 0x18;
+        const REG_AMBIENT_TEMP: u8 = 0x05;
+
+        pub struct Mcp9808 {
+            i2c: I2c<'static, I2C0, Async>,
+        }
+
+        impl Mcp9808 {
+            pub fn new(i2c: I2c<'static, I2C0, Async>) -> Self {
+                Self { i2c }
+            }
+        }
+
+        impl EnvSensor for Mcp9808 {
+            async fn read(&mut self) -> Measurement {
+                let mut raw = [0u8; 2];
+                self.i2c
+                    .write_read(ADDR, &[REG_AMBIENT_TEMP], &mut raw)
+                    .await
+                    .expect("mcp9808: can not read temperature");
+
+                // Upper nibble carries sign/alert flags; mask them off before scaling.
+                let upper = raw[0] & 0x0F;
+                let mut temperature = ((upper as u16) << 8 | raw[1] as u16) as f32 * 0.0625;
+                if raw[0] & 0x10 != 0 {
+                    temperature -= 256.0;
+                }
+
+                Measurement {
+                    temperature_c: Some(temperature),
+                    ..Default::default()
+                }
+            }
+        }
+    }
+
+    /// Analog TMP36 temperature sensor, read through the RP2040's internal ADC.
+    #[cfg(not(any(feature = "sensor-bmp390", feature = "sensor-mcp9808")))]
+    mod tmp36 {
+        use super::{EnvSensor, Measurement};
+        use embassy_rp::adc::{Adc, Async, Channel};
+
+        pub struct Tmp36 {
+            adc: Adc<'static, Async>,
+            channel: Channel<'static>,
+        }
+
+        impl Tmp36 {
+            pub fn new(adc: Adc<'static, Async>, channel: Channel<'static>) -> Self {
+                Self { adc, channel }
+            }
+        }
+
+        impl EnvSensor for Tmp36 {
+            async fn read(&mut self) -> Measurement {
+                let raw = self.adc.read(&mut self.channel).await.unwrap();
+                // RP2040 ADC is 12-bit over a 3.3V reference; TMP36 is 10mV/C with a 500mV offset.
+                let mv = (raw as f32) * 3300.0 / 4095.0;
+                let temperature = (mv - 500.0) / 10.0;
+
+                Measurement {
+                    temperature_c: Some(temperature),
+                    ..Default::default()
+                }
+            }
+        }
+    }
+}
@@ -1,7 +1,8 @@
 //! This example shows how to communicate asynchronous using i2c with external chip.
 //!
 //! It's using embassy's functions directly instead of traits from embedded_hal_async::i2c::I2c.
-//! While most of i2c devices are addressed using 7 bits, an extension allows 10 bits too.
+//! While most of i2c devices are addressed using 7 bits, an extension allows 10 bits too — see
+//! the `i2c10` module below for the `UncomplicatedSensorId::B` device that needs it.
 
 #![no_std]
 #![no_main]
@@ -24,6 +25,7 @@ use embassy_time::Timer;
 use {defmt_rtt as _, panic_probe as _};
 
 use crate::dht20::{initialize, read_temperature_and_humidity};
+use crate::i2c10::I2cTenBitExt;
 
 bind_interrupts!(struct Irqs {
     I2C0_IRQ => InterruptHandler<I2C0>;
@@ -128,6 +130,160 @@ impl From<UncomplicatedSensorId> for u16 {
     }
 }
 
+impl From<UncomplicatedSensorId> for i2c10::Address {
+    fn from(t: UncomplicatedSensorId) -> Self {
+        match t {
+            UncomplicatedSensorId::A(x) => i2c10::Address::SevenBit(<UncomplicatedSensorU8 as Into<u16>>::into(x) as u8),
+            UncomplicatedSensorId::B(x) => i2c10::Address::TenBit(x.into()),
+        }
+    }
+}
+
+/// Minimal 10-bit addressing support for `UncomplicatedSensorId::B`-style devices, exposed as an
+/// extension trait on the existing `i2c::I2c` handle rather than a second, independently-owned
+/// peripheral handle — the same pattern `AdcDmaExt` uses for the ADC in the companion DMA example.
+///
+/// `embassy_rp::i2c::I2c::write`/`read`/`write_read` only take a `u8` address today, and each call
+/// reprograms `IC_TAR` from that `u8`, so they can never reach a true 10-bit target no matter what
+/// `IC_TAR` holds beforehand — a full fix has to widen their address parameter, which lives in
+/// `embassy-rp`'s `i2c.rs` and isn't part of this tree. Until then, this extension borrows the
+/// same `I2c` handle `main` already owns (so the borrow checker rules out anyone using the
+/// peripheral concurrently, unlike reaching for a freshly-summoned `pac::I2C0`) and does the
+/// address and data phases by hand, the way the driver itself would — including the `IC_ENABLE`
+/// gating the DW_apb_i2c controller requires before `IC_TAR`/`IC_CON` changes take effect: both
+/// registers are documented to ignore writes while the controller is enabled.
+mod i2c10 {
+    use embassy_rp::i2c::{Async, I2c};
+    use embassy_rp::pac;
+    use embassy_rp::peripherals::I2C0;
+    use embassy_time::Timer;
+
+    /// A 7-bit or 10-bit I2C target address.
+    #[derive(Clone, Copy)]
+    pub enum Address {
+        SevenBit(u8),
+        TenBit(u16),
+    }
+
+    pub trait I2cTenBitExt {
+        async fn write_10bit(&mut self, addr: Address, data: &[u8]);
+        async fn read_10bit(&mut self, addr: Address, data: &mut [u8]);
+        /// Writes `data`, then reads `buf.len()` bytes back via a repeated start (no stop bit
+        /// between the write and the read phase, so the controller re-sends the address with R
+        /// set instead of releasing the bus).
+        async fn write_read_10bit(&mut self, addr: Address, data: &[u8], buf: &mut [u8]);
+    }
+
+    impl I2cTenBitExt for I2c<'static, I2C0, Async> {
+        async fn write_10bit(&mut self, addr: Address, data: &[u8]) {
+            program_target(addr).await;
+            write_bytes(data).await;
+            restore_7bit_addressing().await;
+        }
+
+        async fn read_10bit(&mut self, addr: Address, data: &mut [u8]) {
+            program_target(addr).await;
+            read_bytes(data).await;
+            restore_7bit_addressing().await;
+        }
+
+        async fn write_read_10bit(&mut self, addr: Address, data: &[u8], buf: &mut [u8]) {
+            program_target(addr).await;
+            write_bytes_no_stop(data).await;
+            read_bytes(buf).await;
+            restore_7bit_addressing().await;
+        }
+    }
+
+    /// `IC_TAR`/`IC_CON` are only sampled by the controller while `IC_ENABLE` is clear, so this
+    /// disables it, waits for `IC_ENABLE_STATUS` to confirm the controller actually stopped,
+    /// reprograms addressing mode and target, then re-enables it.
+    async fn program_target(addr: Address) {
+        let i2c = pac::I2C0;
+
+        i2c.ic_enable().write(|w| w.set_enable(false));
+        while i2c.ic_enable_status().read().ic_en() {
+            Timer::after_micros(1).await;
+        }
+
+        match addr {
+            Address::SevenBit(raw) => {
+                i2c.ic_con().modify(|w| w.set_ic_10bitaddr_master(false));
+                i2c.ic_tar().write(|w| w.set_ic_tar(raw as u16));
+            }
+            Address::TenBit(raw) => {
+                // `IC_TAR` takes the 10-bit address as-is; the controller emits the
+                // `0b11110 | addr[9:8] << 1` / `addr[7:0]` two-byte header (and, for reads, the
+                // repeated-start re-send of the first byte with R set) automatically.
+                i2c.ic_con().modify(|w| w.set_ic_10bitaddr_master(true));
+                i2c.ic_tar().write(|w| w.set_ic_tar(raw));
+            }
+        }
+
+        i2c.ic_enable().write(|w| w.set_enable(true));
+    }
+
+    /// Clears `IC_CON.IC_10BITADDR_MASTER` after a 10-bit transfer finishes, the same
+    /// `IC_ENABLE`-gated way `program_target` sets it. Without this, the controller stays in
+    /// 10-bit addressing mode afterwards; the plain `embassy_rp::i2c::I2c::write`/`read`/
+    /// `write_read` calls `main` also uses for the DHT20 only ever reprogram `IC_TAR`, not
+    /// `IC_CON`, so a 7-bit transfer run right after a 10-bit one would otherwise still be sent
+    /// with the master addressing 10-bit.
+    async fn restore_7bit_addressing() {
+        let i2c = pac::I2C0;
+
+        i2c.ic_enable().write(|w| w.set_enable(false));
+        while i2c.ic_enable_status().read().ic_en() {
+            Timer::after_micros(1).await;
+        }
+
+        i2c.ic_con().modify(|w| w.set_ic_10bitaddr_master(false));
+
+        i2c.ic_enable().write(|w| w.set_enable(true));
+    }
+
+    async fn write_bytes_inner(data: &[u8], stop_at_end: bool) {
+        let i2c = pac::I2C0;
+        for (i, &byte) in data.iter().enumerate() {
+            while !i2c.ic_status().read().tfnf() {
+                Timer::after_micros(10).await;
+            }
+            i2c.ic_data_cmd().write(|w| {
+                w.set_dat(byte);
+                w.set_stop(stop_at_end && i == data.len() - 1);
+            });
+        }
+        while !i2c.ic_status().read().tfe() {
+            Timer::after_micros(10).await;
+        }
+    }
+
+    /// Writes `data`, asserting `STOP` after the last byte.
+    async fn write_bytes(data: &[u8]) {
+        write_bytes_inner(data, true).await;
+    }
+
+    /// Writes `data` without a trailing `STOP`, so the next transfer gets a repeated start.
+    async fn write_bytes_no_stop(data: &[u8]) {
+        write_bytes_inner(data, false).await;
+    }
+
+    /// Reads `data.len()` bytes from the already-programmed target into `data`.
+    async fn read_bytes(data: &mut [u8]) {
+        let i2c = pac::I2C0;
+        for (i, byte) in data.iter_mut().enumerate() {
+            i2c.ic_data_cmd().write(|w| {
+                w.set_cmd(true);
+                w.set_stop(i == data.len() - 1);
+            });
+            while i2c.ic_rxflr().read().rxflr() == 0 {
+                Timer::after_micros(10).await;
+            }
+            *byte = i2c.ic_data_cmd().read().dat();
+        }
+    }
+}
+
 // embassy_rp::bind_interrupts!(struct Irqs {
 //     I2C1_IRQ => InterruptHandler<embassy_rp::peripherals::I2C1>;
 // });
@@ -148,6 +304,14 @@ async fn main(_spawner: Spawner) {
         info!("temperature = {}C", temperature);
         info!("humidity = {}%", humidity);
 
+        // Our hypothetical `UncomplicatedSensorId::B` device lives at a 10-bit address, which the
+        // `i2c::I2c` handle's own `write`/`read` can't reach; `read_10bit` borrows the same handle
+        // exclusively and drives the address/data phases by hand instead.
+        let mut reply = [0u8; 2];
+        i2c.read_10bit(UncomplicatedSensorId::B(UncomplicatedSensorU16::Other).into(), &mut reply)
+            .await;
+        info!("10-bit sensor reply: {}", u16::from_be_bytes(reply));
+
         Timer::after_millis(500).await;
     }
 }
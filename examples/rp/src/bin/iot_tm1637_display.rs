@@ -0,0 +1,174 @@
+//! Shows the potentiometer reading from `iot_adc` on a 4-digit TM1637 display instead of only
+//! logging it over `defmt`, so the value can be read without a debugger attached.
+//!
+//! Wiring: potentiometer on pin 26, TM1637 CLK on pin 18, TM1637 DIO on pin 19.
+
+#![no_std]
+#![no_main]
+
+use defmt::*;
+use embassy_executor::Spawner;
+use embassy_rp::adc::{Adc, Config, InterruptHandler};
+use embassy_rp::gpio::Pull;
+use embassy_rp::{adc, bind_interrupts};
+use embassy_time::Timer;
+use {defmt_rtt as _, panic_probe as _};
+
+use tm1637::Tm1637;
+
+bind_interrupts!(struct Irqs {
+    ADC_IRQ_FIFO => InterruptHandler;
+});
+
+#[embassy_executor::main]
+async fn main(_spawner: Spawner) {
+    let p = embassy_rp::init(Default::default());
+
+    info!("Setting up ADC");
+    let mut adc = Adc::new(p.ADC, Irqs, Config::default());
+    let mut p26 = adc::Channel::new_pin(p.PIN_26, Pull::None);
+
+    info!("Setting up TM1637 display");
+    let mut display = Tm1637::new(p.PIN_18, p.PIN_19);
+    display.set_brightness(4);
+
+    loop {
+        let value = adc.read(&mut p26).await.unwrap();
+        info!("ADC value: {}", value);
+        display.display_number(value).await;
+
+        Timer::after_millis(100).await;
+    }
+}
+
+/// TM1637 4-digit 7-segment display driver, bit-banged over two `OutputOpenDrain` pins.
+///
+/// Mirrors the style of the in-tree `dht20` module: a small, self-contained async driver for a
+/// cheap sensor/peripheral that doesn't have a blessed `embassy-rp` HAL driver, built directly on
+/// GPIO. The TM1637 protocol looks like I2C (start/stop conditions, LSB-first bytes, an ACK bit)
+/// but has no device address, since CLK/DIO are a dedicated point-to-point link.
+mod tm1637 {
+    use embassy_rp::gpio::{Level, OutputOpenDrain, Pin};
+    use embassy_time::Timer;
+
+    const CMD_DATA_WRITE_AUTO_INCREMENT: u8 = 0x40;
+    const CMD_ADDRESS_C0: u8 = 0xC0;
+    const CMD_DISPLAY_CTRL: u8 = 0x88;
+
+    /// Segment patterns for digits 0-9 and hex digits A-F, indexed by value.
+    pub const DIGITS: [u8; 16] = [
+        0b0011_1111, // 0
+        0b0000_0110, // 1
+        0b0101_1011, // 2
+        0b0100_1111, // 3
+        0b0110_0110, // 4
+        0b0110_1101, // 5
+        0b0111_1101, // 6
+        0b0000_0111, // 7
+        0b0111_1111, // 8
+        0b0110_1111, // 9
+        0b0111_0111, // A
+        0b0111_1100, // b
+        0b0011_1001, // C
+        0b0101_1110, // d
+        0b0111_1001, // E
+        0b0111_0001, // F
+    ];
+
+    pub struct Tm1637<'d> {
+        clk: OutputOpenDrain<'d>,
+        dio: OutputOpenDrain<'d>,
+        brightness: u8,
+    }
+
+    impl<'d> Tm1637<'d> {
+        pub fn new(clk: impl Pin, dio: impl Pin) -> Self {
+            Self {
+                clk: OutputOpenDrain::new(clk, Level::High),
+                dio: OutputOpenDrain::new(dio, Level::High),
+                brightness: 7,
+            }
+        }
+
+        /// Sets the display brightness, clamped to the 0-7 range the TM1637 supports.
+        pub fn set_brightness(&mut self, brightness: u8) {
+            self.brightness = brightness.min(7);
+        }
+
+        /// Renders `value` right-aligned across the 4 digits (e.g. `42` becomes `  42`).
+        pub async fn display_number(&mut self, value: u16) {
+            let mut digits = [0u16; 4];
+            let mut remaining = value.min(9999);
+            for slot in digits.iter_mut().rev() {
+                *slot = remaining % 10;
+                remaining /= 10;
+            }
+
+            let mut segments = [0u8; 4];
+            let mut leading = true;
+            for (i, (&digit, segment)) in digits.iter().zip(segments.iter_mut()).enumerate() {
+                leading &= digit == 0 && i != digits.len() - 1;
+                *segment = if leading { 0 } else { DIGITS[digit as usize] };
+            }
+            self.write_digits(&segments).await;
+        }
+
+        async fn write_digits(&mut self, segments: &[u8; 4]) {
+            self.start().await;
+            self.write_byte(CMD_DATA_WRITE_AUTO_INCREMENT).await;
+            self.stop().await;
+
+            self.start().await;
+            self.write_byte(CMD_ADDRESS_C0).await;
+            for &segment in segments {
+                self.write_byte(segment).await;
+            }
+            self.stop().await;
+
+            self.start().await;
+            self.write_byte(CMD_DISPLAY_CTRL | self.brightness).await;
+            self.stop().await;
+        }
+
+        async fn start(&mut self) {
+            self.clk.set_high();
+            self.dio.set_high();
+            Timer::after_micros(2).await;
+            self.dio.set_low();
+            Timer::after_micros(2).await;
+            self.clk.set_low();
+        }
+
+        async fn stop(&mut self) {
+            self.dio.set_low();
+            Timer::after_micros(2).await;
+            self.clk.set_high();
+            Timer::after_micros(2).await;
+            self.dio.set_high();
+            Timer::after_micros(2).await;
+        }
+
+        /// Clocks out `byte` LSB-first, then releases DIO for one more clock so the TM1637 can
+        /// drive its ACK; the ACK bit is not read.
+        async fn write_byte(&mut self, byte: u8) {
+            for bit in 0..8 {
+                if (byte >> bit) & 1 == 1 {
+                    self.dio.set_high();
+                } else {
+                    self.dio.set_low();
+                }
+                Timer::after_micros(2).await;
+                self.clk.set_high();
+                Timer::after_micros(2).await;
+                self.clk.set_low();
+            }
+
+            // Release DIO and pulse CLK once more so the TM1637 can pull DIO low to ACK.
+            self.dio.set_high();
+            Timer::after_micros(2).await;
+            self.clk.set_high();
+            Timer::after_micros(2).await;
+            self.clk.set_low();
+        }
+    }
+}
@@ -0,0 +1,415 @@
+//! Exposes the potentiometer/DHT20-style readings to a host PC over USB instead of only
+//! `defmt` logs, using a small postcard-rpc-style framing on top of `embassy_usb`'s bulk
+//! endpoints: every request/response is a `postcard`-serialized struct prefixed with a varint
+//! sequence number and an endpoint key, so the same request/response types can be shared between
+//! this firmware and a host-side crate.
+//!
+//! Wiring: potentiometer on pin 26, as in `iot_adc`; DHT20 on I2C0 (pins 0/1), as in
+//! `iot_i2c_async_embassy`.
+
+#![no_std]
+#![no_main]
+
+use defmt::*;
+use embassy_executor::Spawner;
+use embassy_rp::adc::{Adc, Config as AdcConfig, InterruptHandler as AdcInterruptHandler};
+use embassy_rp::bind_interrupts;
+use embassy_rp::gpio::Pull;
+use embassy_rp::i2c::{self, InterruptHandler as I2cInterruptHandler};
+use embassy_rp::peripherals::{I2C0, USB};
+use embassy_rp::usb::{Driver, InterruptHandler as UsbInterruptHandler};
+use embassy_rp::{adc, usb};
+use embassy_sync::blocking_mutex::raw::ThreadModeRawMutex;
+use embassy_sync::channel::Channel;
+use embassy_sync::mutex::Mutex;
+use embassy_time::Timer;
+use embassy_usb::UsbDevice;
+use {defmt_rtt as _, panic_probe as _};
+
+use dht20::{initialize, read_temperature_and_humidity};
+use rpc::{Endpoint, RpcServer};
+
+bind_interrupts!(struct Irqs {
+    ADC_IRQ_FIFO => AdcInterruptHandler;
+    USBCTRL_IRQ => UsbInterruptHandler<USB>;
+    I2C0_IRQ => I2cInterruptHandler<I2C0>;
+});
+
+/// Averaged potentiometer values waiting to be pushed to the `SubscribeAdc` endpoint.
+static ADC_STREAM: Channel<ThreadModeRawMutex, f32, 8> = Channel::new();
+
+/// Latest DHT20 reading `GetTemperature`/`GetHumidity` reply with. `None` until the first
+/// successful read completes.
+static LATEST_READING: Mutex<ThreadModeRawMutex, Option<(f32, f32)>> = Mutex::new(None);
+
+#[embassy_executor::main]
+async fn main(spawner: Spawner) {
+    let p = embassy_rp::init(Default::default());
+
+    info!("Setting up ADC");
+    let adc = Adc::new(p.ADC, Irqs, AdcConfig::default());
+    let p26 = adc::Channel::new_pin(p.PIN_26, Pull::None);
+
+    info!("Setting up DHT20");
+    let i2c = i2c::I2c::new_async(p.I2C0, p.PIN_1, p.PIN_0, Irqs, i2c::Config::default());
+
+    info!("Setting up USB");
+    let driver = Driver::new(p.USB, Irqs);
+    let mut config = embassy_usb::Config::new(0xc0de, 0xcafe);
+    config.manufacturer = Some("embassy");
+    config.product = Some("iot-usb-rpc");
+
+    static CONFIG_DESCRIPTOR: static_cell::StaticCell<[u8; 256]> = static_cell::StaticCell::new();
+    static BOS_DESCRIPTOR: static_cell::StaticCell<[u8; 256]> = static_cell::StaticCell::new();
+    static CONTROL_BUF: static_cell::StaticCell<[u8; 64]> = static_cell::StaticCell::new();
+
+    let mut builder = embassy_usb::Builder::new(
+        driver,
+        config,
+        CONFIG_DESCRIPTOR.init([0; 256]),
+        BOS_DESCRIPTOR.init([0; 256]),
+        &mut [],
+        CONTROL_BUF.init([0; 64]),
+    );
+
+    let rpc_class = {
+        let mut function = builder.function(0xff, 0, 0);
+        let mut interface = function.interface();
+        let mut alt = interface.alt_setting(0xff, 0, 0, None);
+        let read_ep = alt.endpoint_bulk_out(64);
+        let write_ep = alt.endpoint_bulk_in(64);
+        drop(function);
+        rpc::RpcClass::new(read_ep, write_ep)
+    };
+
+    let usb = builder.build();
+
+    // Split the class so the IN endpoint can be shared between this request/reply loop and the
+    // independent `push_adc_stream` task below — both write replies/pushes onto the same bulk IN
+    // endpoint, so the split-off writer half is wrapped in a `Mutex` to serialize them.
+    let (mut rpc_reader, rpc_writer) = rpc_class.split();
+    static RPC_WRITER: static_cell::StaticCell<Mutex<ThreadModeRawMutex, rpc::RpcWriter<'static, Driver<'static, USB>>>> =
+        static_cell::StaticCell::new();
+    let rpc_writer = &*RPC_WRITER.init(Mutex::new(rpc_writer));
+
+    spawner.spawn(run_usb(usb)).unwrap();
+    spawner.spawn(read_adc_value(adc, p26)).unwrap();
+    spawner.spawn(read_dht20(i2c)).unwrap();
+    spawner.spawn(push_adc_stream(rpc_writer)).unwrap();
+
+    let mut server = RpcServer::new();
+    loop {
+        match rpc_reader.next_request().await {
+            Ok(request) => server.handle(request, rpc_writer).await,
+            Err(_) => {
+                // The host isn't connected or the endpoint stalled; `run_usb` keeps servicing
+                // enumeration concurrently, so just retry once it's ready again.
+                Timer::after_millis(10).await;
+            }
+        }
+    }
+}
+
+/// Pushes every averaged potentiometer reading onto `SubscribeAdc` as soon as `read_adc_value`
+/// produces it, independently of whether a request is currently in flight on the main RPC loop —
+/// a genuine continuous stream rather than a drain that only runs when a request happens to
+/// arrive.
+#[embassy_executor::task]
+async fn push_adc_stream(writer: &'static Mutex<ThreadModeRawMutex, rpc::RpcWriter<'static, Driver<'static, USB>>>) {
+    loop {
+        let value = ADC_STREAM.receive().await;
+        let _ = writer.lock().await.push::<rpc::SubscribeAdc>(&value).await;
+    }
+}
+
+/// Drives the USB stack (enumeration, suspend/resume, endpoint polling). This has to run
+/// concurrently with the RPC request loop, not just while waiting for a connection:
+/// `UsbDevice::run_until_suspend` only returns once the host suspends the device, and nothing
+/// else polls the endpoints while it's doing so, so anything that calls it once up front and then
+/// moves on to handling requests would leave the USB peripheral unserviced.
+#[embassy_executor::task]
+async fn run_usb(mut usb: UsbDevice<'static, Driver<'static, USB>>) {
+    usb.run().await;
+}
+
+#[embassy_executor::task]
+async fn read_adc_value(mut adc: Adc<'static, adc::Async>, mut p26: adc::Channel<'static>) {
+    let mut measurements = [0u16; 10];
+    let mut pos = 0;
+    loop {
+        measurements[pos] = adc.read(&mut p26).await.unwrap();
+        pos = (pos + 1) % 10;
+
+        if pos == 0 {
+            let average = measurements.iter().map(|&v| v as u32).sum::<u32>() as f32 / 10.0;
+            ADC_STREAM.send(average).await;
+        }
+
+        Timer::after_millis(100).await;
+    }
+}
+
+/// Keeps `LATEST_READING` current so `GetTemperature`/`GetHumidity` have a real reading to reply
+/// with instead of a hardcoded placeholder.
+#[embassy_executor::task]
+async fn read_dht20(mut i2c: i2c::I2c<'static, I2C0, i2c::Async>) {
+    let ready = initialize(&mut i2c).await;
+    info!("DHT20 ready: {}", ready);
+
+    loop {
+        let reading = read_temperature_and_humidity(&mut i2c).await;
+        *LATEST_READING.lock().await = Some(reading);
+        Timer::after_millis(500).await;
+    }
+}
+
+/// DHT20 sensor: datasheet: https://cdn.sparkfun.com/assets/8/a/1/5/0/DHT20.pdf
+///
+/// Identical to the `dht20` module in `iot_i2c_async_embassy`.
+mod dht20 {
+    use defmt::debug;
+    use embassy_rp::i2c::{Async, I2c};
+    use embassy_rp::peripherals::I2C0;
+    use embassy_time::Timer;
+    use embedded_hal_async::i2c::I2c as I2cAsync;
+
+    const DHT20_I2C_ADDR: u8 = 0x38;
+    const DHT20_GET_STATUS: u8 = 0x71;
+    const DHT20_READ_DATA: [u8; 3] = [0xAC, 0x33, 0x00];
+
+    const DIVISOR: f32 = 2u32.pow(20) as f32;
+    const TEMP_DIVISOR: f32 = DIVISOR / 200.0;
+
+    pub async fn initialize(i2c: &mut I2c<'static, I2C0, Async>) -> bool {
+        Timer::after_millis(100).await;
+        let mut data = [0x0; 1];
+        i2c.write_read(DHT20_I2C_ADDR, &[DHT20_GET_STATUS], &mut data)
+            .await
+            .expect("Can not read status");
+
+        data[0] & 0x18 == 0x18
+    }
+
+    async fn read_data(i2c: &mut I2c<'static, I2C0, Async>) -> [u8; 6] {
+        let mut data = [0x0; 6];
+
+        for _ in 0..10 {
+            i2c.write(DHT20_I2C_ADDR, &DHT20_READ_DATA)
+                .await
+                .expect("Can not write data");
+            Timer::after_millis(80).await;
+
+            i2c.read(DHT20_I2C_ADDR, &mut data).await.expect("Can not read data");
+
+            if data[0] >> 7 == 0 {
+                break;
+            }
+        }
+
+        data
+    }
+
+    pub async fn read_temperature_and_humidity(i2c: &mut I2c<'static, I2C0, Async>) -> (f32, f32) {
+        let data = read_data(i2c).await;
+        debug!("data = {:?}", data);
+
+        let raw_hum_data = ((data[1] as u32) << 12) + ((data[2] as u32) << 4) + (((data[3] & 0xf0) >> 4) as u32);
+        debug!("raw_humidity_data = {:x}", raw_hum_data);
+        let humidity = (raw_hum_data as f32) / DIVISOR * 100.0;
+
+        let raw_temp_data = (((data[3] as u32) & 0xf) << 16) + ((data[4] as u32) << 8) + (data[5] as u32);
+        debug!("raw_temperature_data = {:x}", raw_temp_data);
+        let temperature = (raw_temp_data as f32) / TEMP_DIVISOR - 50.0;
+
+        (temperature, humidity)
+    }
+}
+
+/// A minimal postcard-rpc-style framing on top of `embassy_usb` bulk endpoints.
+///
+/// Each request/response is prefixed with a varint sequence number (so the host can match
+/// replies that arrive out of order) and an endpoint key (a hash of the endpoint's path, as
+/// `postcard-rpc` does, so the firmware and host don't need to agree on numeric endpoint IDs).
+mod rpc {
+    use embassy_sync::blocking_mutex::raw::ThreadModeRawMutex;
+    use embassy_sync::mutex::Mutex;
+    use embassy_usb::driver::{Driver, Endpoint as _, EndpointError, EndpointIn, EndpointOut};
+    use postcard::to_slice;
+    use serde::{Deserialize, Serialize};
+
+    /// FNV-1a hash of an endpoint's path string, matching `postcard-rpc`'s key derivation.
+    pub const fn key_of(path: &str) -> u32 {
+        let bytes = path.as_bytes();
+        let mut hash: u32 = 0x811c_9dc5;
+        let mut i = 0;
+        while i < bytes.len() {
+            hash ^= bytes[i] as u32;
+            hash = hash.wrapping_mul(0x0100_0193);
+            i += 1;
+        }
+        hash
+    }
+
+    pub trait Endpoint {
+        const PATH: &'static str;
+        type Request: Serialize + for<'a> Deserialize<'a>;
+        type Response: Serialize + for<'a> Deserialize<'a>;
+    }
+
+    pub struct GetTemperature;
+    impl Endpoint for GetTemperature {
+        const PATH: &'static str = "sensors/temperature";
+        type Request = ();
+        type Response = f32;
+    }
+
+    pub struct GetHumidity;
+    impl Endpoint for GetHumidity {
+        const PATH: &'static str = "sensors/humidity";
+        type Request = ();
+        type Response = f32;
+    }
+
+    /// Streaming endpoint: each push carries one averaged potentiometer reading from
+    /// `super::ADC_STREAM`, rather than a single request/response pair.
+    pub struct SubscribeAdc;
+    impl Endpoint for SubscribeAdc {
+        const PATH: &'static str = "sensors/adc/subscribe";
+        type Request = ();
+        type Response = f32;
+    }
+
+    pub struct Frame {
+        pub seq: u32,
+        pub key: u32,
+        pub payload: [u8; 64],
+        pub payload_len: usize,
+    }
+
+    pub struct RpcClass<'d, D: Driver<'d>> {
+        read_ep: D::EndpointOut,
+        write_ep: D::EndpointIn,
+    }
+
+    impl<'d, D: Driver<'d>> RpcClass<'d, D> {
+        pub fn new(read_ep: D::EndpointOut, write_ep: D::EndpointIn) -> Self {
+            Self { read_ep, write_ep }
+        }
+
+        /// Splits the class into independent reader/writer halves so the IN endpoint can be
+        /// shared (behind a `Mutex`) with a task that pushes subscription updates, while the OUT
+        /// endpoint stays exclusively owned by the request loop.
+        pub fn split(self) -> (RpcReader<'d, D>, RpcWriter<'d, D>) {
+            (RpcReader { read_ep: self.read_ep }, RpcWriter {
+                write_ep: self.write_ep,
+                next_seq: 0,
+            })
+        }
+    }
+
+    pub struct RpcReader<'d, D: Driver<'d>> {
+        read_ep: D::EndpointOut,
+    }
+
+    impl<'d, D: Driver<'d>> RpcReader<'d, D> {
+        pub async fn next_request(&mut self) -> Result<Frame, EndpointError> {
+            let mut buf = [0u8; 64];
+            let n = self.read_ep.read(&mut buf).await?;
+
+            let mut cursor = &buf[..n];
+            let seq: u32 = leb128_read(&mut cursor);
+            let key: u32 = leb128_read(&mut cursor);
+
+            let mut payload = [0u8; 64];
+            let payload_len = cursor.len();
+            payload[..payload_len].copy_from_slice(cursor);
+
+            Ok(Frame {
+                seq,
+                key,
+                payload,
+                payload_len,
+            })
+        }
+    }
+
+    pub struct RpcWriter<'d, D: Driver<'d>> {
+        write_ep: D::EndpointIn,
+        next_seq: u32,
+    }
+
+    impl<'d, D: Driver<'d>> RpcWriter<'d, D> {
+        pub async fn reply<E: Endpoint>(&mut self, seq: u32, response: &E::Response) -> Result<(), EndpointError> {
+            let mut buf = [0u8; 64];
+            let mut len = leb128_write(seq, &mut buf);
+            len += leb128_write(key_of(E::PATH), &mut buf[len..]);
+
+            let encoded = to_slice(response, &mut buf[len..]).expect("rpc: response does not fit in one frame");
+            let total = len + encoded.len();
+            self.write_ep.write(&buf[..total]).await
+        }
+
+        pub async fn push<E: Endpoint>(&mut self, response: &E::Response) -> Result<(), EndpointError> {
+            let seq = self.next_seq;
+            self.next_seq = self.next_seq.wrapping_add(1);
+            self.reply::<E>(seq, response).await
+        }
+    }
+
+    pub struct RpcServer;
+
+    impl RpcServer {
+        pub fn new() -> Self {
+            Self
+        }
+
+        /// Handles one request/reply pair. `SubscribeAdc` isn't answered here: it's a genuine
+        /// push stream driven independently by `push_adc_stream`, not a request/response endpoint,
+        /// so a request arriving on it is simply ignored.
+        pub async fn handle<'d, D: Driver<'d>>(
+            &mut self,
+            request: Frame,
+            writer: &Mutex<ThreadModeRawMutex, RpcWriter<'d, D>>,
+        ) {
+            if request.key == key_of(GetTemperature::PATH) {
+                let temperature = super::LATEST_READING.lock().await.map(|(t, _)| t).unwrap_or(f32::NAN);
+                let _ = writer.lock().await.reply::<GetTemperature>(request.seq, &temperature).await;
+            } else if request.key == key_of(GetHumidity::PATH) {
+                let humidity = super::LATEST_READING.lock().await.map(|(_, h)| h).unwrap_or(f32::NAN);
+                let _ = writer.lock().await.reply::<GetHumidity>(request.seq, &humidity).await;
+            }
+        }
+    }
+
+    fn leb128_read(buf: &mut &[u8]) -> u32 {
+        let mut value = 0u32;
+        let mut shift = 0;
+        loop {
+            let (&byte, rest) = buf.split_first().expect("rpc: truncated varint");
+            *buf = rest;
+            value |= ((byte & 0x7f) as u32) << shift;
+            if byte & 0x80 == 0 {
+                break;
+            }
+            shift += 7;
+        }
+        value
+    }
+
+    fn leb128_write(mut value: u32, buf: &mut [u8]) -> usize {
+        let mut i = 0;
+        loop {
+            let mut byte = (value & 0x7f) as u8;
+            value >>= 7;
+            if value != 0 {
+                byte |= 0x80;
+            }
+            buf[i] = byte;
+            i += 1;
+            if value == 0 {
+                break;
+            }
+        }
+        i
+    }
+}